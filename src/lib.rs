@@ -55,4 +55,280 @@ mod tests {
         assert_eq!(elem, 2);
         assert_eq!(v.len(), 3);
     }
+
+    #[test]
+    fn into_iter_yields_elements_by_value_in_order() {
+        let mut v: NaiveVec<i64> = NaiveVec::new();
+        v.push(1);
+        v.push(2);
+        v.push(3);
+
+        let collected: Vec<i64> = v.into_iter().collect();
+        assert_eq!(collected, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn into_iter_drop_runs_destructors_of_unconsumed_elements() {
+        let mut v: NaiveVec<String> = NaiveVec::new();
+        v.push(String::from("a"));
+        v.push(String::from("b"));
+        v.push(String::from("c"));
+
+        let mut iter = v.into_iter();
+        assert_eq!(iter.next(), Some(String::from("a")));
+        // remaining "b" and "c" are dropped here without leaking.
+    }
+
+    #[test]
+    fn drop_runs_element_destructors() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        struct DropCounter(Rc<Cell<usize>>);
+        impl Drop for DropCounter {
+            fn drop(&mut self) {
+                self.0.set(self.0.get() + 1);
+            }
+        }
+
+        let count = Rc::new(Cell::new(0));
+        let mut v: NaiveVec<DropCounter> = NaiveVec::new();
+        v.push(DropCounter(count.clone()));
+        v.push(DropCounter(count.clone()));
+        v.push(DropCounter(count.clone()));
+
+        drop(v);
+        assert_eq!(count.get(), 3);
+    }
+
+    #[test]
+    fn custom_allocator_backs_naive_vec_push_pop() {
+        use crate::naive_vec::{AllocError, Allocator, Global};
+        use std::alloc::Layout;
+        use std::cell::Cell;
+        use std::ptr::NonNull;
+
+        struct CountingAlloc {
+            allocations: Cell<usize>,
+        }
+
+        unsafe impl Allocator for CountingAlloc {
+            fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+                self.allocations.set(self.allocations.get() + 1);
+                Global.allocate(layout)
+            }
+
+            unsafe fn grow(
+                &self,
+                ptr: NonNull<u8>,
+                old_layout: Layout,
+                new_layout: Layout,
+            ) -> Result<NonNull<[u8]>, AllocError> {
+                self.allocations.set(self.allocations.get() + 1);
+                unsafe { Global.grow(ptr, old_layout, new_layout) }
+            }
+
+            unsafe fn shrink(
+                &self,
+                ptr: NonNull<u8>,
+                old_layout: Layout,
+                new_layout: Layout,
+            ) -> Result<NonNull<[u8]>, AllocError> {
+                self.allocations.set(self.allocations.get() + 1);
+                unsafe { Global.shrink(ptr, old_layout, new_layout) }
+            }
+
+            unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+                unsafe { Global.deallocate(ptr, layout) }
+            }
+        }
+
+        let alloc = CountingAlloc { allocations: Cell::new(0) };
+        let mut v: NaiveVec<i64, CountingAlloc> = NaiveVec::new_in(alloc);
+        v.push(1);
+        v.push(2);
+        v.push(3);
+        assert_eq!(v.pop(), Some(3));
+        assert_eq!(&v[..], &[1, 2]);
+    }
+
+    #[test]
+    fn try_push_grows_and_succeeds_under_normal_conditions() {
+        let mut v: NaiveVec<i64> = NaiveVec::new();
+        assert_eq!(v.try_push(1), Ok(()));
+        assert_eq!(v.try_push(2), Ok(()));
+        assert_eq!(&v[..], &[1, 2]);
+    }
+
+    #[test]
+    fn try_reserve_grows_capacity_without_changing_len() {
+        let mut v: NaiveVec<i64> = NaiveVec::new();
+        v.push(1);
+        assert_eq!(v.try_reserve(16), Ok(()));
+        assert_eq!(v.len(), 1);
+        assert_eq!(&v[..], &[1]);
+    }
+
+    #[test]
+    fn try_push_and_try_reserve_report_alloc_error_instead_of_aborting() {
+        use crate::naive_vec::{AllocError, Allocator, TryReserveError};
+        use std::alloc::Layout;
+        use std::ptr::NonNull;
+
+        struct FailingAlloc;
+
+        unsafe impl Allocator for FailingAlloc {
+            fn allocate(&self, _layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+                Err(AllocError)
+            }
+
+            unsafe fn grow(
+                &self,
+                _ptr: NonNull<u8>,
+                _old_layout: Layout,
+                _new_layout: Layout,
+            ) -> Result<NonNull<[u8]>, AllocError> {
+                Err(AllocError)
+            }
+
+            unsafe fn shrink(
+                &self,
+                _ptr: NonNull<u8>,
+                _old_layout: Layout,
+                _new_layout: Layout,
+            ) -> Result<NonNull<[u8]>, AllocError> {
+                Err(AllocError)
+            }
+
+            unsafe fn deallocate(&self, _ptr: NonNull<u8>, _layout: Layout) {}
+        }
+
+        let mut v: NaiveVec<i64, FailingAlloc> = NaiveVec::new_in(FailingAlloc);
+        assert_eq!(
+            v.try_push(1),
+            Err(TryReserveError::AllocError { layout: Layout::array::<i64>(1).unwrap() })
+        );
+        assert_eq!(v.len(), 0);
+        assert_eq!(&v[..], &[] as &[i64]);
+
+        assert_eq!(
+            v.try_reserve(4),
+            Err(TryReserveError::AllocError { layout: Layout::array::<i64>(4).unwrap() })
+        );
+        assert_eq!(v.len(), 0);
+        assert_eq!(&v[..], &[] as &[i64]);
+    }
+
+    #[test]
+    fn with_capacity_preallocates_without_adding_elements() {
+        let v: NaiveVec<i64> = NaiveVec::with_capacity(8);
+        assert_eq!(v.len(), 0);
+        assert_eq!(&v[..], &[] as &[i64]);
+    }
+
+    #[test]
+    fn reserve_and_reserve_exact_do_not_change_len() {
+        let mut v: NaiveVec<i64> = NaiveVec::new();
+        v.push(1);
+        v.push(2);
+
+        v.reserve(10);
+        assert_eq!(v.len(), 2);
+        assert_eq!(&v[..], &[1, 2]);
+
+        v.reserve_exact(20);
+        assert_eq!(v.len(), 2);
+        assert_eq!(&v[..], &[1, 2]);
+    }
+
+    #[test]
+    fn shrink_to_fit_preserves_elements() {
+        let mut v: NaiveVec<i64> = NaiveVec::with_capacity(64);
+        v.push(1);
+        v.push(2);
+        v.push(3);
+
+        v.shrink_to_fit();
+        assert_eq!(v.len(), 3);
+        assert_eq!(&v[..], &[1, 2, 3]);
+    }
+
+    #[test]
+    fn drain_yields_all_elements_and_empties_the_vec() {
+        let mut v: NaiveVec<i64> = NaiveVec::new();
+        v.push(1);
+        v.push(2);
+        v.push(3);
+
+        let drained: Vec<i64> = v.drain().collect();
+        assert_eq!(drained, vec![1, 2, 3]);
+        assert_eq!(v.len(), 0);
+    }
+
+    #[test]
+    fn drain_drop_runs_destructors_of_unconsumed_elements() {
+        let mut v: NaiveVec<String> = NaiveVec::new();
+        v.push(String::from("a"));
+        v.push(String::from("b"));
+        v.push(String::from("c"));
+
+        {
+            let mut drain = v.drain();
+            assert_eq!(drain.next(), Some(String::from("a")));
+            // remaining "b" and "c" are dropped when `drain` goes out of scope.
+        }
+        assert_eq!(v.len(), 0);
+    }
+
+    #[test]
+    fn drain_keep_rest_restores_unyielded_tail() {
+        let mut v: NaiveVec<i64> = NaiveVec::new();
+        v.push(1);
+        v.push(2);
+        v.push(3);
+        v.push(4);
+
+        let mut drain = v.drain();
+        assert_eq!(drain.next(), Some(1));
+        assert_eq!(drain.next(), Some(2));
+        drain.keep_rest();
+
+        assert_eq!(v.len(), 2);
+        assert_eq!(&v[..], &[3, 4]);
+    }
+
+    #[test]
+    fn into_iter_supports_rev_and_exact_size() {
+        let mut v: NaiveVec<i64> = NaiveVec::new();
+        v.push(1);
+        v.push(2);
+        v.push(3);
+
+        let mut iter = v.into_iter();
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next_back(), Some(3));
+        assert_eq!(iter.next(), Some(2));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+    }
+
+    #[test]
+    fn drain_supports_rev() {
+        let mut v: NaiveVec<i64> = NaiveVec::new();
+        v.push(1);
+        v.push(2);
+        v.push(3);
+
+        let drained: Vec<i64> = v.drain().rev().collect();
+        assert_eq!(drained, vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn into_iter_and_drain_are_send() {
+        fn assert_send<T: Send>() {}
+
+        assert_send::<crate::naive_vec::IntoIter<String>>();
+        assert_send::<crate::naive_vec::Drain<'static, String>>();
+    }
 }
@@ -2,25 +2,126 @@
 use std::{ptr::NonNull, marker::PhantomData};
 use std::ops::{Deref, DerefMut};
 use std::alloc;
+use std::mem;
 use std::ptr;
-use std::alloc::{Layout, alloc, realloc};
+use std::alloc::{Layout, alloc, realloc, dealloc};
+
+// error returned by a fallible Allocator method, no payload since callers
+// can only report it or bail out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AllocError;
+
+// shaped like allocator-api2 / the unstable std::alloc::Allocator so
+// RawVec/NaiveVec can be backed by something other than the global
+// allocator (an arena, a bump allocator, a counting allocator in tests).
+// implementors must hand back pointers valid for layout and must not
+// deallocate behind the caller's back, since RawVec relies on that.
+pub unsafe trait Allocator {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError>;
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError>;
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError>;
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout);
+}
+
+// forwards to std::alloc's global allocator, matching the behavior RawVec
+// had before it grew an allocator parameter.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Global;
+
+unsafe impl Allocator for Global {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        if layout.size() == 0 {
+            return Ok(NonNull::slice_from_raw_parts(NonNull::dangling(), 0));
+        }
+        let ptr = unsafe { alloc(layout) };
+        let ptr = NonNull::new(ptr).ok_or(AllocError)?;
+        Ok(NonNull::slice_from_raw_parts(ptr, layout.size()))
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        let raw = unsafe { realloc(ptr.as_ptr(), old_layout, new_layout.size()) };
+        let raw = NonNull::new(raw).ok_or(AllocError)?;
+        Ok(NonNull::slice_from_raw_parts(raw, new_layout.size()))
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // std::alloc::realloc handles both growing and shrinking.
+        let raw = unsafe { realloc(ptr.as_ptr(), old_layout, new_layout.size()) };
+        let raw = NonNull::new(raw).ok_or(AllocError)?;
+        Ok(NonNull::slice_from_raw_parts(raw, new_layout.size()))
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        unsafe { dealloc(ptr.as_ptr(), layout) }
+    }
+}
+
+// error returned by the fallible try_* family (try_push, try_reserve)
+// instead of aborting the process on allocation failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryReserveError {
+    // requested capacity (or its byte layout) doesn't fit in the address
+    // space, independent of whatever the allocator would say.
+    CapacityOverflow,
+    // layout was valid but the allocator itself couldn't satisfy it.
+    AllocError { layout: Layout },
+}
+
+impl std::fmt::Display for TryReserveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TryReserveError::CapacityOverflow => {
+                write!(f, "memory allocation failed because the computed capacity exceeded the collection's maximum")
+            }
+            TryReserveError::AllocError { layout } => {
+                write!(f, "memory allocation of {} bytes failed", layout.size())
+            }
+        }
+    }
+}
 
-// RawVec<T> was separated from NaiveVec<T>
+impl std::error::Error for TryReserveError {}
+
+// RawVec<T, A> was separated from NaiveVec<T, A>
 // since there are overlapping functionalities when implementing
 // IntoIter trait
-struct RawVec<T> {
+struct RawVec<T, A: Allocator = Global> {
     ptr: NonNull<T>,
     cap: usize,
+    alloc: A,
     // for explicit drop-check analysis
     // ossociate dropping over NaiveVec<T> with dropping over T
     _marker: PhantomData<T>,
 }
 
-unsafe impl<T: Send> Send for RawVec<T> {}
-unsafe impl<T: Sync> Sync for RawVec<T> {}
+unsafe impl<T: Send, A: Allocator + Send> Send for RawVec<T, A> {}
+unsafe impl<T: Sync, A: Allocator + Sync> Sync for RawVec<T, A> {}
 
-impl<T> RawVec<T> {
-    fn new() -> Self {
+impl<T, A: Allocator> RawVec<T, A> {
+    fn new_in(alloc: A) -> Self {
         // check if T is zero-sized type or not then set cap to usize::MAX
         // since every operation on ptr with zero-sized type is no-op
         // to guard against capacity overflow.
@@ -28,53 +129,121 @@ impl<T> RawVec<T> {
         RawVec {
             ptr: NonNull::dangling(),
             cap: cap,
+            alloc,
             _marker: PhantomData
         }
     }
 
 
-    // grow is where actual allocation happens.
-    fn grow(&mut self) {
-
+    // try_grow_to is the fallible core that both the doubling growth path
+    // (try_grow/grow) and the precise-capacity path (reserve_exact,
+    // with_capacity) funnel through.
+    fn try_grow_to(&mut self, new_cap: usize) -> Result<(), TryReserveError> {
         // for zero-sized type, any operation should not reach here
-        // thus, to call grow() on zero-sized type is invalid and rejected here.
-        assert!(std::mem::size_of::<T>() != 0, "capacity overflow");
+        // thus, to call try_grow_to() on zero-sized type is invalid and rejected here.
+        if std::mem::size_of::<T>() == 0 {
+            return Err(TryReserveError::CapacityOverflow);
+        }
 
-        // part 1: create memory layout for allocation from set cap
-        let (new_cap, new_layout) = if self.cap == 0 {
-            (1, Layout::array::<T>(1).unwrap())
-        } else {
-            let new_cap = self.cap * 2;
-            let new_layout = Layout::array::<T>(new_cap).unwrap();
-            (new_cap, new_layout)
-        };
+        let new_layout = Layout::array::<T>(new_cap).map_err(|_| TryReserveError::CapacityOverflow)?;
 
         // need to check against isize::MAX here as LLVM's GEP instruction
         // use signed integer, thus limitations are reflected here as well.
-        assert!(new_layout.size() <= isize::MAX as usize, "grow allocation is too large");
+        if new_layout.size() > isize::MAX as usize {
+            return Err(TryReserveError::CapacityOverflow);
+        }
 
-        // part 2: actual allocation
+        // actual allocation, routed through the configured allocator
+        // instead of calling std::alloc directly.
         let new_ptr = if self.cap == 0 {
-            unsafe { alloc(new_layout) }
+            self.alloc.allocate(new_layout)
         } else {
             // unwrap() here should never fail since it checks if number of bytes is <= usize::MAX
-            // but layout created here always passed assertion with <= isize::MAX above
+            // but layout created here always passed the bound check above
             let old_layout = Layout::array::<T>(self.cap).unwrap();
-            let old_ptr = self.ptr.as_ptr() as *mut u8;
-            unsafe { realloc(old_ptr, old_layout, new_layout.size()) }
+            let old_ptr = self.ptr.cast();
+            unsafe { self.alloc.grow(old_ptr, old_layout, new_layout) }
         };
 
-        // abort if allocation fails, using alloc error handler provided by std::alloc
-        self.ptr = match NonNull::new(new_ptr as *mut T) {
-            Some(ptr) => ptr,
-            None => alloc::handle_alloc_error(new_layout),
+        match new_ptr {
+            Ok(ptr) => {
+                self.ptr = ptr.cast();
+                self.cap = new_cap;
+                Ok(())
+            }
+            Err(AllocError) => Err(TryReserveError::AllocError { layout: new_layout }),
+        }
+    }
+
+    // grow_to is where actual allocation happens for the infallible API;
+    // it's just try_grow_to() that panics on capacity overflow and aborts
+    // on allocation failure instead of returning a Result.
+    fn grow_to(&mut self, new_cap: usize) {
+        match self.try_grow_to(new_cap) {
+            Ok(()) => {}
+            Err(TryReserveError::CapacityOverflow) => panic!("capacity overflow"),
+            Err(TryReserveError::AllocError { layout }) => alloc::handle_alloc_error(layout),
+        }
+    }
+
+    // shrink_to reclaims slack by reallocating down to exactly `new_cap`
+    // (or freeing outright when `new_cap` is 0).
+    fn shrink_to(&mut self, new_cap: usize) {
+        assert!(new_cap <= self.cap, "shrink_to can't grow capacity");
+
+        // ZST capacity is pinned at usize::MAX; there's no allocation to shrink.
+        if std::mem::size_of::<T>() == 0 || new_cap == self.cap {
+            return;
+        }
+
+        if new_cap == 0 {
+            let layout = Layout::array::<T>(self.cap).unwrap();
+            unsafe { self.alloc.deallocate(self.ptr.cast(), layout); }
+            self.ptr = NonNull::dangling();
+            self.cap = 0;
+            return;
+        }
+
+        let old_layout = Layout::array::<T>(self.cap).unwrap();
+        let new_layout = Layout::array::<T>(new_cap).unwrap();
+        let old_ptr = self.ptr.cast();
+        let new_ptr = unsafe { self.alloc.shrink(old_ptr, old_layout, new_layout) };
+        self.ptr = match new_ptr {
+            Ok(ptr) => ptr.cast(),
+            Err(_) => alloc::handle_alloc_error(new_layout),
         };
         self.cap = new_cap;
     }
-    
+
+    // try_grow is the fallible core of grow(): same doubling scheme, same
+    // isize::MAX bound, but it reports failure instead of aborting so
+    // callers that want to survive OOM (try_push/try_reserve) can recover.
+    fn try_grow(&mut self) -> Result<(), TryReserveError> {
+        if std::mem::size_of::<T>() == 0 {
+            return Err(TryReserveError::CapacityOverflow);
+        }
+        let new_cap = if self.cap == 0 {
+            1
+        } else {
+            self.cap.checked_mul(2).ok_or(TryReserveError::CapacityOverflow)?
+        };
+        self.try_grow_to(new_cap)
+    }
+
+    // grow is where actual allocation happens for the infallible API;
+    // it's just try_grow() that panics on capacity overflow and aborts
+    // on allocation failure instead of returning a Result.
+    fn grow(&mut self) {
+        match self.try_grow() {
+            Ok(()) => {}
+            Err(TryReserveError::CapacityOverflow) => panic!("capacity overflow"),
+            Err(TryReserveError::AllocError { layout }) => alloc::handle_alloc_error(layout),
+        }
+    }
+
 }
 
-impl<T> Drop for RawVec<T> {
+impl<T, A: Allocator> Drop for RawVec<T, A> {
     // no-op if dropping on zero-sized type or unallocated pointer.
     fn drop(&mut self) {
         let elem_size = std::mem::size_of::<T>();
@@ -83,16 +252,32 @@ impl<T> Drop for RawVec<T> {
         }
 
         let layout = Layout::array::<T>(self.cap).unwrap();
-        unsafe { alloc::dealloc(self.ptr.as_ptr() as *mut u8, layout); }
+        unsafe { self.alloc.deallocate(self.ptr.cast(), layout); }
     }
 }
 
-pub struct NaiveVec<T> {
-    buf: RawVec<T>,
+pub struct NaiveVec<T, A: Allocator = Global> {
+    buf: RawVec<T, A>,
     len: usize,
 }
 
-impl<T> NaiveVec<T> {
+impl<T> NaiveVec<T, Global> {
+    pub fn new() -> Self {
+        NaiveVec::new_in(Global)
+    }
+
+    // preallocates room for capacity elements up front instead of growing
+    // one push at a time from an empty buffer.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut buf: RawVec<T, Global> = RawVec::new_in(Global);
+        if capacity > 0 && std::mem::size_of::<T>() != 0 {
+            buf.grow_to(capacity);
+        }
+        NaiveVec { buf, len: 0 }
+    }
+}
+
+impl<T, A: Allocator> NaiveVec<T, A> {
     fn ptr(&self) -> *mut T {
         self.buf.ptr.as_ptr()
     }
@@ -100,8 +285,10 @@ impl<T> NaiveVec<T> {
         self.buf.cap
     }
 
-    pub fn new() -> Self {
-        NaiveVec { buf: RawVec::new(), len: 0 }
+    // empty NaiveVec backed by a caller-supplied allocator instead of
+    // the Global default.
+    pub fn new_in(alloc: A) -> Self {
+        NaiveVec { buf: RawVec::new_in(alloc), len: 0 }
     }
 
     pub fn push(&mut self, elem: T) {
@@ -119,6 +306,68 @@ impl<T> NaiveVec<T> {
         self.len = self.len + 1;
     }
 
+    // fallible counterpart of push(): returns Err instead of aborting the
+    // process when the backing allocation can't grow to fit the new element.
+    pub fn try_push(&mut self, elem: T) -> Result<(), TryReserveError> {
+        if self.len == self.cap() {
+            self.buf.try_grow()?;
+        }
+        unsafe {
+            ptr::write(self.ptr().add(self.len), elem);
+        }
+        self.len = self.len + 1;
+        Ok(())
+    }
+
+    // fallible, amortized reserve: same target as reserve() (max(cap*2,
+    // len+additional)), but returns Err instead of aborting on overflow/OOM.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let required = self.len.checked_add(additional).ok_or(TryReserveError::CapacityOverflow)?;
+        if self.cap() >= required {
+            return Ok(());
+        }
+        let new_cap = if self.cap() == 0 {
+            required
+        } else {
+            std::cmp::max(self.cap() * 2, required)
+        };
+        self.buf.try_grow_to(new_cap)
+    }
+
+    // amortized reserve: grows to max(cap*2, len+additional) so repeated
+    // small reserves don't each trigger their own reallocation.
+    pub fn reserve(&mut self, additional: usize) {
+        let required = self.len.checked_add(additional).expect("capacity overflow");
+        if self.cap() >= required {
+            return;
+        }
+        let new_cap = if self.cap() == 0 {
+            required
+        } else {
+            std::cmp::max(self.cap() * 2, required)
+        };
+        self.buf.grow_to(new_cap);
+    }
+
+    // reserves room for exactly additional more elements, not the
+    // amortized cap*2 that reserve() rounds up to.
+    pub fn reserve_exact(&mut self, additional: usize) {
+        let required = self.len.checked_add(additional).expect("capacity overflow");
+        if self.cap() >= required {
+            return;
+        }
+        self.buf.grow_to(required);
+    }
+
+    // shrinks the backing allocation down to exactly len, releasing any
+    // reserved slack.
+    pub fn shrink_to_fit(&mut self) {
+        if self.cap() == self.len {
+            return;
+        }
+        self.buf.shrink_to(self.len);
+    }
+
     pub fn pop(&mut self) -> Option<T> {
         match self.len {
             0 => None,
@@ -161,6 +410,15 @@ impl<T> NaiveVec<T> {
         }
     }
 
+    // removes and returns every element as an iterator. len is zeroed
+    // immediately so a leaked (mem::forget'ten) Drain can't double-free.
+    pub fn drain(&mut self) -> Drain<'_, T, A> {
+        let iter = unsafe { RawValIter::new(self) };
+        let vec = NonNull::from(&mut *self);
+        self.len = 0;
+        Drain { vec, iter, _marker: PhantomData }
+    }
+
 }
 
 struct RawValIter<T> {
@@ -168,6 +426,9 @@ struct RawValIter<T> {
     end: *const T,
 }
 
+unsafe impl<T: Send> Send for RawValIter<T> {}
+unsafe impl<T: Sync> Sync for RawValIter<T> {}
+
 impl<T> RawValIter<T> {
     unsafe fn new(slice: &[T]) -> Self {
         RawValIter {
@@ -199,7 +460,7 @@ impl<T> Iterator for RawValIter<T> {
                 Some(result)
             }
         }
-        
+
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
@@ -210,19 +471,44 @@ impl<T> Iterator for RawValIter<T> {
     }
 }
 
-impl<T> Drop for NaiveVec<T> {
+impl<T> DoubleEndedIterator for RawValIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.start == self.end {
+            None
+        } else {
+            unsafe {
+                self.end = if std::mem::size_of::<T>() == 0 {
+                    (self.end as usize - 1) as *const T
+                } else {
+                    self.end.offset(-1)
+                };
+                Some(ptr::read(self.end))
+            }
+        }
+    }
+}
+
+// size_hint above is already exact, so ExactSizeIterator is free; start
+// and end only ever converge, never diverge again once equal, so the
+// iterator is fused too.
+impl<T> ExactSizeIterator for RawValIter<T> {}
+impl<T> std::iter::FusedIterator for RawValIter<T> {}
+
+impl<T, A: Allocator> Drop for NaiveVec<T, A> {
     fn drop(&mut self) {
-        // in example, it calls pop until None is yielded
-        // but here we set len = 0 instead then drop
-        // while let Some(_) =  self.pop() {}
-        self.len = 0;
+        // walk the live slice with RawValIter so every T's own Drop runs;
+        // RawVec's Drop (run right after this) only frees the allocation
+        // and knows nothing about T's destructor.
+        unsafe {
+            for _ in RawValIter::new(self) {}
+        }
     }
 }
 
 // slice trait implementation is done
 // via Deref and DerefMut trait implementation
 
-impl<T> Deref for NaiveVec<T> {
+impl<T, A: Allocator> Deref for NaiveVec<T, A> {
     type Target = [T];
     fn deref(&self) -> &Self::Target {
         unsafe {
@@ -231,7 +517,7 @@ impl<T> Deref for NaiveVec<T> {
     }
 }
 
-impl<T> DerefMut for NaiveVec<T> {
+impl<T, A: Allocator> DerefMut for NaiveVec<T, A> {
     fn deref_mut(&mut self) -> &mut Self::Target {
         unsafe {
             std::slice::from_raw_parts_mut(self.ptr(), self.len)
@@ -239,12 +525,77 @@ impl<T> DerefMut for NaiveVec<T> {
     }
 }
 
-pub struct Drain<'a, T: 'a> {
-    vec: PhantomData<&'a mut NaiveVec<T>>,
+pub struct Drain<'a, T: 'a, A: Allocator = Global> {
+    // kept so keep_rest() can reach back into the vec to restore its len
+    // and find the start of its buffer.
+    vec: NonNull<NaiveVec<T, A>>,
+    iter: RawValIter<T>,
+    _marker: PhantomData<&'a mut NaiveVec<T, A>>,
+}
+
+unsafe impl<'a, T: Send, A: Allocator + Send> Send for Drain<'a, T, A> {}
+unsafe impl<'a, T: Sync, A: Allocator + Sync> Sync for Drain<'a, T, A> {}
+
+impl<'a, T, A: Allocator> Drain<'a, T, A> {
+    // keeps the elements this Drain hasn't yielded yet instead of
+    // dropping them: shifts them down to the front of the buffer and
+    // restores the vec's len to cover them (the drain_keep_rest behavior
+    // from alloc's Drain).
+    pub fn keep_rest(self) {
+        unsafe {
+            // skip our own Drop (which would drain the remainder) since
+            // we're about to hand it back to the vec instead.
+            let mut this = mem::ManuallyDrop::new(self);
+
+            let unyielded_len = this.iter.size_hint().0;
+            let unyielded_ptr = this.iter.start;
+
+            let vec = this.vec.as_mut();
+            let buf_start = vec.ptr();
+            if unyielded_len > 0 {
+                ptr::copy(unyielded_ptr, buf_start, unyielded_len);
+            }
+            vec.len = unyielded_len;
+        }
+    }
+}
+
+impl<'a, T, A: Allocator> Iterator for Drain<'a, T, A> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, T, A: Allocator> DoubleEndedIterator for Drain<'a, T, A> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back()
+    }
+}
+
+impl<'a, T, A: Allocator> ExactSizeIterator for Drain<'a, T, A> {}
+impl<'a, T, A: Allocator> std::iter::FusedIterator for Drain<'a, T, A> {}
+
+impl<'a, T, A: Allocator> Drop for Drain<'a, T, A> {
+    fn drop(&mut self) {
+        // consume whatever the caller left unyielded so their destructors
+        // still run; the vec's len was already zeroed in drain().
+        for _ in &mut self.iter {}
+    }
+}
+
+// IntoIter hands out T by value. It keeps the RawVec allocation alive
+// (via _buf) for as long as RawValIter is walking it, even though it
+// never touches _buf directly.
+pub struct IntoIter<T, A: Allocator = Global> {
+    _buf: RawVec<T, A>,
     iter: RawValIter<T>,
 }
 
-impl<'a, T> Iterator for Drain<'a, T> {
+impl<T, A: Allocator> Iterator for IntoIter<T, A> {
     type Item = T;
     fn next(&mut self) -> Option<Self::Item> {
         self.iter.next()
@@ -252,4 +603,39 @@ impl<'a, T> Iterator for Drain<'a, T> {
     fn size_hint(&self) -> (usize, Option<usize>) {
         self.iter.size_hint()
     }
-}
\ No newline at end of file
+}
+
+impl<T, A: Allocator> DoubleEndedIterator for IntoIter<T, A> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back()
+    }
+}
+
+impl<T, A: Allocator> ExactSizeIterator for IntoIter<T, A> {}
+impl<T, A: Allocator> std::iter::FusedIterator for IntoIter<T, A> {}
+
+impl<T, A: Allocator> Drop for IntoIter<T, A> {
+    fn drop(&mut self) {
+        // drain whatever the caller didn't consume so their destructors
+        // still run before _buf's allocation is freed.
+        for _ in &mut self.iter {}
+    }
+}
+
+impl<T, A: Allocator> IntoIterator for NaiveVec<T, A> {
+    type Item = T;
+    type IntoIter = IntoIter<T, A>;
+
+    fn into_iter(self) -> IntoIter<T, A> {
+        unsafe {
+            let iter = RawValIter::new(&self);
+            let buf = ptr::read(&self.buf);
+
+            // we moved buf out of self above, so self's Drop must not
+            // run (it would free the allocation out from under iter).
+            mem::forget(self);
+
+            IntoIter { iter, _buf: buf }
+        }
+    }
+}